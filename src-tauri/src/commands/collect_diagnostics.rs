@@ -0,0 +1,15 @@
+use std::sync::atomic::Ordering;
+
+use tauri::State;
+
+use crate::structs::{ConnectionState, Diagnostics};
+use crate::ADDR;
+
+#[tauri::command]
+pub fn collect_diagnostics(state: State<'_, ConnectionState>) -> Diagnostics {
+    Diagnostics {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        service_address: ADDR.to_string(),
+        service_connected: state.connected.load(Ordering::SeqCst),
+    }
+}
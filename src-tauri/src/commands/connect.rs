@@ -1,8 +1,8 @@
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 
 #[tauri::command]
@@ -12,7 +12,7 @@ pub async fn connect(
     password: String,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::Connect {
         uuid,
         username,
@@ -22,14 +22,5 @@ pub async fn connect(
         keep_alive_timeout: Default::default(),
         session_security_settings: Default::default(),
     };
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
@@ -1,8 +1,8 @@
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 
 #[tauri::command]
@@ -13,7 +13,7 @@ pub async fn del_kv(
     key: String,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::LocalDBDeleteKV {
         uuid,
         cid,
@@ -21,14 +21,5 @@ pub async fn del_kv(
         key,
     };
 
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
@@ -1,6 +1,6 @@
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
@@ -10,17 +10,8 @@ pub async fn disconnect(
     cid: u64,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::Disconnect { uuid, cid };
 
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
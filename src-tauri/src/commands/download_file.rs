@@ -1,10 +1,10 @@
 use std::path::PathBuf;
 
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 
 #[tauri::command]
@@ -15,7 +15,7 @@ pub async fn download_file(
     delete_on_pull: bool,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::DownloadFile {
         virtual_path: PathBuf::from(virtual_path),
         transfer_security_level: Default::default(),
@@ -24,14 +24,5 @@ pub async fn download_file(
         uuid,
     };
 
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
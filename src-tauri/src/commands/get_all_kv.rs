@@ -1,8 +1,8 @@
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 
 #[tauri::command]
@@ -12,21 +12,12 @@ pub async fn get_all_kv(
     peer_cid: Option<u64>,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::LocalDBGetAllKV {
         uuid,
         cid,
         peer_cid,
     };
 
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
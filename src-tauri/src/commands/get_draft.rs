@@ -0,0 +1,24 @@
+use citadel_workspace_types::InternalServicePayload;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::helpers::send_payload::send_payload;
+use crate::structs::{ConnectionState, DraftScope};
+
+#[tauri::command]
+pub async fn get_draft(
+    uuid: String,
+    cid: u64,
+    scope: DraftScope,
+    state: State<'_, ConnectionState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
+    let payload = InternalServicePayload::LocalDBGetKV {
+        uuid,
+        cid,
+        peer_cid: None,
+        key: scope.key(),
+    };
+
+    send_payload(state.inner(), &payload).await
+}
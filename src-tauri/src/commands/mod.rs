@@ -1,14 +1,19 @@
 pub mod clear_all_kv;
+pub mod clear_draft;
+pub mod collect_diagnostics;
 pub mod connect;
 pub mod del_kv;
 pub mod disconnect;
 pub mod download_file;
 pub mod get_all_kv;
+pub mod get_draft;
 pub mod get_kv;
 pub mod message;
 pub mod peer_connect;
 pub mod peer_disconnect;
 pub mod peer_register;
 pub mod register;
+pub mod save_draft;
 pub mod send_file;
+pub mod service_status;
 pub mod set_kv;
@@ -1,6 +1,6 @@
+use crate::helpers::send_payload::send_payload;
 use crate::{structs::ConnectionState, ADDR};
 use citadel_workspace_types::InternalServicePayload;
-use futures::SinkExt;
 use tauri::State;
 use uuid::Uuid;
 
@@ -12,7 +12,7 @@ pub async fn register(
     proposed_password: String,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::Register {
         uuid,
         server_addr: ADDR,
@@ -22,14 +22,5 @@ pub async fn register(
         connect_after_register: Default::default(),
         default_security_settings: Default::default(),
     };
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
@@ -1,9 +1,9 @@
 use citadel_workspace_types::{InternalServicePayload, TransferType};
-use futures::SinkExt;
 use std::path::PathBuf;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::helpers::send_payload::send_payload;
 use crate::structs::ConnectionState;
 
 #[tauri::command]
@@ -14,7 +14,7 @@ pub async fn send_file(
     chunk_size: u64,
     state: State<'_, ConnectionState>,
 ) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&uuid).unwrap();
+    let uuid = Uuid::parse_str(&uuid).map_err(|err| err.to_string())?;
     let payload = InternalServicePayload::SendFile {
         uuid,
         source: PathBuf::from(source),
@@ -23,14 +23,5 @@ pub async fn send_file(
         transfer_type: TransferType::FileTransfer,
     };
 
-    let _ = state
-        .sink
-        .lock()
-        .await
-        .as_mut()
-        .unwrap()
-        .send(bincode2::serialize(&payload).unwrap().into())
-        .await;
-
-    Ok(())
+    send_payload(state.inner(), &payload).await
 }
@@ -0,0 +1,11 @@
+use std::sync::atomic::Ordering;
+
+use tauri::State;
+
+use crate::structs::ConnectionState;
+
+/// Returns whether the internal service connection is currently open.
+#[tauri::command]
+pub fn service_status(state: State<'_, ConnectionState>) -> bool {
+    state.connected.load(Ordering::SeqCst)
+}
@@ -0,0 +1,28 @@
+/// Destination for events sent to the frontend, so packet handling can run without a live window.
+pub(crate) trait EventSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), tauri::Error>;
+}
+
+impl EventSink for tauri::Window {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), tauri::Error> {
+        tauri::Window::emit(self, event, payload)
+    }
+}
+
+/// Sink that records every emitted `(event, payload)` pair instead of sending it.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct RecordingSink {
+    pub events: std::sync::Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+#[cfg(test)]
+impl EventSink for RecordingSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), tauri::Error> {
+        self.events
+            .lock()
+            .unwrap()
+            .push((event.to_string(), payload));
+        Ok(())
+    }
+}
@@ -1 +1,3 @@
+pub mod event_sink;
+pub mod send_payload;
 pub mod types;
@@ -0,0 +1,47 @@
+use citadel_workspace_types::InternalServicePayload;
+use futures::SinkExt;
+use std::sync::atomic::Ordering;
+
+use crate::structs::ConnectionState;
+
+/// Serializes `payload` and writes it to the internal service connection.
+pub(crate) async fn send_payload(
+    state: &ConnectionState,
+    payload: &InternalServicePayload,
+) -> Result<(), String> {
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return Err("Error: the application is shutting down".to_string());
+    }
+    let packet = bincode2::serialize(payload).map_err(|err| err.to_string())?;
+    let mut sink = state.sink.lock().await;
+    let sink = sink
+        .as_mut()
+        .ok_or_else(|| "Error: not connected to the internal service".to_string())?;
+    sink.send(packet.into())
+        .await
+        .map_err(|err| format!("Error: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::send_payload;
+    use crate::structs::ConnectionState;
+    use citadel_workspace_types::InternalServicePayload;
+    use std::sync::atomic::Ordering;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn rejects_payloads_while_shutting_down() {
+        let state = ConnectionState::default();
+        state.shutting_down.store(true, Ordering::SeqCst);
+        let payload = InternalServicePayload::Disconnect {
+            uuid: Uuid::nil(),
+            cid: 0,
+        };
+
+        assert_eq!(
+            send_payload(&state, &payload).await,
+            Err("Error: the application is shutting down".to_string())
+        );
+    }
+}
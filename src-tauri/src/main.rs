@@ -9,28 +9,58 @@ use citadel_logging::{error, setup_log};
 use citadel_workspace_lib::wrap_tcp_conn;
 use citadel_workspace_types::InternalServiceResponse;
 use commands::{
-    clear_all_kv::clear_all_kv, connect::connect, del_kv::del_kv, disconnect::disconnect,
-    download_file::download_file, get_all_kv::get_all_kv, get_kv::get_kv, message::message,
+    clear_all_kv::clear_all_kv, clear_draft::clear_draft, collect_diagnostics::collect_diagnostics,
+    connect::connect, del_kv::del_kv, disconnect::disconnect, download_file::download_file,
+    get_all_kv::get_all_kv, get_draft::get_draft, get_kv::get_kv, message::message,
     peer_connect::peer_connect, peer_disconnect::peer_disconnect, peer_register::peer_redister,
-    register::register, send_file::send_file, set_kv::set_kv,
+    register::register, save_draft::save_draft, send_file::send_file,
+    service_status::service_status, set_kv::set_kv,
 };
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
+use helpers::event_sink::EventSink;
 use std::error::Error;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use structs::ConnectionState;
 use tauri::{Manager, State};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 
-async fn send_response(packet: BytesMut, window: tauri::Window) -> Result<(), Box<dyn Error>> {
-    let _ = window.emit(
+async fn send_response(packet: BytesMut, sink: &impl EventSink) -> Result<(), Box<dyn Error>> {
+    let response = bincode2::deserialize::<InternalServiceResponse>(&packet)?;
+    let _ = sink.emit(
         "packet",
-        serde_json::to_string(&bincode2::deserialize::<InternalServiceResponse>(&packet)?)?,
+        serde_json::Value::String(serde_json::to_string(&response)?),
     );
     Ok(())
 }
 
+/// Tears down the service connection tagged `generation` after its stream ended,
+/// unless a newer connection has already replaced it.
+async fn on_service_stream_end(state: &ConnectionState, generation: u64, events: &impl EventSink) {
+    let mut current = state.sink.lock().await;
+    if state.generation.load(Ordering::SeqCst) == generation {
+        *current = None;
+        state.connected.store(false, Ordering::SeqCst);
+        let _ = events.emit("service:health", serde_json::Value::Bool(false));
+    }
+}
+
+/// Marks the service connection as gone, then takes and closes the sink.
+/// Gives up after `deadline`, since a send blocked on a wedged service can hold
+/// the sink lock indefinitely.
+async fn close_service_connection(state: &ConnectionState, deadline: Duration) {
+    state.connected.store(false, Ordering::SeqCst);
+    let close = async {
+        let sink = state.sink.lock().await.take();
+        if let Some(mut sink) = sink {
+            let _ = sink.close().await;
+        }
+    };
+    let _ = timeout(deadline, close).await;
+}
+
 pub static ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 3000);
 
 #[tauri::command]
@@ -38,6 +68,9 @@ async fn open_tcp_conn(
     conn_state: State<'_, ConnectionState>,
     window: tauri::Window,
 ) -> Result<String, String> {
+    if conn_state.shutting_down.load(Ordering::SeqCst) {
+        return Err("Error: the application is shutting down".to_string());
+    }
     let connection = TcpStream::connect(ADDR);
     match timeout(Duration::from_millis(3000), connection)
         .await
@@ -46,16 +79,29 @@ async fn open_tcp_conn(
         Ok(conn) => {
             let framed = wrap_tcp_conn(conn);
             let (sink, mut stream) = framed.split();
-            *conn_state.sink.lock().await = Some(sink);
+            let generation = {
+                let mut current = conn_state.sink.lock().await;
+                // Shutdown may have started while connecting; don't put a sink
+                // back after the close sequence took it
+                if conn_state.shutting_down.load(Ordering::SeqCst) {
+                    return Err("Error: the application is shutting down".to_string());
+                }
+                *current = Some(sink);
+                conn_state.connected.store(true, Ordering::SeqCst);
+                conn_state.generation.fetch_add(1, Ordering::SeqCst) + 1
+            };
+            let _ = window.emit("service:health", true);
             let service_to_gui = async move {
                 while let Some(packet) = stream.next().await {
                     if let Ok(packet) = packet {
                         print!("{packet:?}");
-                        if let Err(e) = send_response(packet, window.clone()).await {
+                        if let Err(e) = send_response(packet, &window).await {
                             error!(e)
                         }
                     }
                 }
+                on_service_stream_end(&window.state::<ConnectionState>(), generation, &window)
+                    .await;
             };
             tauri::async_runtime::spawn(service_to_gui);
             Ok(format!("Connected"))
@@ -67,10 +113,7 @@ async fn open_tcp_conn(
 #[tokio::main]
 async fn main() {
     tauri::Builder::default()
-        .manage(ConnectionState {
-            sink: Default::default(),
-            stream: Default::default(),
-        })
+        .manage(ConnectionState::default())
         .setup(|app| {
             setup_log();
             #[cfg(debug_assertions)] // only include this code on debug builds
@@ -81,6 +124,28 @@ async fn main() {
             }
             Ok(())
         })
+        .on_window_event(|event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event.event() {
+                // Hold the close so new commands are rejected and the service
+                // connection is released before exiting
+                api.prevent_close();
+                let window = event.window().clone();
+                let state = window.state::<ConnectionState>();
+                if state.shutting_down.swap(true, Ordering::SeqCst) {
+                    // Shutdown already in progress and bounded by its deadline
+                    return;
+                }
+                let _ = window.emit("app:closing", ());
+                tauri::async_runtime::spawn(async move {
+                    close_service_connection(
+                        &window.state::<ConnectionState>(),
+                        Duration::from_millis(3000),
+                    )
+                    .await;
+                    window.app_handle().exit(0);
+                });
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             open_tcp_conn,
             connect,
@@ -96,8 +161,133 @@ async fn main() {
             get_kv,
             clear_all_kv,
             send_file,
-            download_file
+            download_file,
+            save_draft,
+            get_draft,
+            clear_draft,
+            service_status,
+            collect_diagnostics
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{close_service_connection, on_service_stream_end, send_response};
+    use crate::helpers::event_sink::RecordingSink;
+    use crate::structs::ConnectionState;
+    use bytes::BytesMut;
+    use citadel_workspace_lib::wrap_tcp_conn;
+    use citadel_workspace_types::InternalServiceResponse;
+    use futures::StreamExt;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::oneshot;
+    use tokio::time::timeout;
+    use uuid::Uuid;
+
+    /// State holding a live sink to a local listener, tagged with `generation`.
+    async fn connected_state(generation: u64) -> (ConnectionState, TcpListener) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let conn = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (sink, _) = wrap_tcp_conn(conn).split();
+        let state = ConnectionState::default();
+        *state.sink.lock().await = Some(sink);
+        state.connected.store(true, Ordering::SeqCst);
+        state.generation.store(generation, Ordering::SeqCst);
+        (state, listener)
+    }
+
+    #[tokio::test]
+    async fn send_response_emits_one_packet_event() {
+        let response = InternalServiceResponse::ServiceConnectionAccepted { id: Uuid::nil() };
+        let packet = BytesMut::from(&bincode2::serialize(&response).unwrap()[..]);
+        let sink = RecordingSink::default();
+
+        send_response(packet, &sink).await.unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![(
+                "packet".to_string(),
+                serde_json::Value::String(serde_json::to_string(&response).unwrap())
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn send_response_rejects_malformed_packet() {
+        let packet = BytesMut::from(&[0xff, 0xff, 0xff][..]);
+        let sink = RecordingSink::default();
+
+        assert!(send_response(packet, &sink).await.is_err());
+        assert!(sink.events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stale_stream_end_keeps_newer_connection() {
+        let (state, _listener) = connected_state(2).await;
+        let events = RecordingSink::default();
+
+        on_service_stream_end(&state, 1, &events).await;
+
+        assert!(state.sink.lock().await.is_some());
+        assert!(state.connected.load(Ordering::SeqCst));
+        assert!(events.events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn current_stream_end_clears_connection() {
+        let (state, _listener) = connected_state(2).await;
+        let events = RecordingSink::default();
+
+        on_service_stream_end(&state, 2, &events).await;
+
+        assert!(state.sink.lock().await.is_none());
+        assert!(!state.connected.load(Ordering::SeqCst));
+        assert_eq!(
+            *events.events.lock().unwrap(),
+            vec![("service:health".to_string(), serde_json::Value::Bool(false))]
+        );
+    }
+
+    #[tokio::test]
+    async fn close_takes_sink_and_clears_connected() {
+        let (state, _listener) = connected_state(1).await;
+
+        close_service_connection(&state, Duration::from_millis(500)).await;
+
+        assert!(state.sink.lock().await.is_none());
+        assert!(!state.connected.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn close_finishes_at_deadline_when_sink_lock_is_held() {
+        let (state, _listener) = connected_state(1).await;
+        let state = Arc::new(state);
+        let (locked_tx, locked_rx) = oneshot::channel();
+        let holder = Arc::clone(&state);
+        // Stand-in for a send stuck on a wedged service
+        tokio::spawn(async move {
+            let _guard = holder.sink.lock().await;
+            let _ = locked_tx.send(());
+            std::future::pending::<()>().await;
+        });
+        locked_rx.await.unwrap();
+
+        let closed = timeout(
+            Duration::from_secs(2),
+            close_service_connection(&state, Duration::from_millis(100)),
+        )
+        .await;
+
+        assert!(closed.is_ok());
+        assert!(!state.connected.load(Ordering::SeqCst));
+    }
+}
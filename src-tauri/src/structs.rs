@@ -1,6 +1,68 @@
+use std::sync::atomic::{AtomicBool, AtomicU64};
+
 use crate::helpers::types::{ConnSink, ConnStream};
 
+#[derive(Default)]
 pub struct ConnectionState {
     pub sink: ConnSink,
     pub stream: ConnStream,
+    /// Whether the internal service connection is open. Kept outside the sink
+    /// mutex so it can be read while a send is blocked.
+    pub connected: AtomicBool,
+    /// Incremented on every `open_tcp_conn`, so a reader task only tears down
+    /// the connection it was spawned for.
+    pub generation: AtomicU64,
+    /// Set once the main window starts closing; new commands are rejected.
+    pub shutting_down: AtomicBool,
+}
+
+/// Identifies the channel a locally stored message draft belongs to.
+#[derive(serde::Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum DraftScope {
+    Group { group_id: String },
+    Peer { peer_cid: u64 },
+}
+
+impl DraftScope {
+    /// Local KV key under which the draft for this scope is stored.
+    pub fn key(&self) -> String {
+        match self {
+            DraftScope::Group { group_id } => format!("draft.group.{group_id}"),
+            DraftScope::Peer { peer_cid } => format!("draft.peer.{peer_cid}"),
+        }
+    }
+}
+
+/// Client-side state snapshot returned by `collect_diagnostics` for support reports.
+#[derive(serde::Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub service_address: String,
+    pub service_connected: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DraftScope;
+
+    #[test]
+    fn group_scope_deserializes_and_keys() {
+        let scope: DraftScope = serde_json::from_str(r#"{"group_id":"g"}"#).unwrap();
+        assert!(matches!(scope, DraftScope::Group { ref group_id } if group_id == "g"));
+        assert_eq!(scope.key(), "draft.group.g");
+    }
+
+    #[test]
+    fn peer_scope_deserializes_and_keys() {
+        let scope: DraftScope = serde_json::from_str(r#"{"peer_cid":5}"#).unwrap();
+        assert!(matches!(scope, DraftScope::Peer { peer_cid: 5 }));
+        assert_eq!(scope.key(), "draft.peer.5");
+    }
+
+    #[test]
+    fn ambiguous_scope_is_rejected() {
+        let scope = serde_json::from_str::<DraftScope>(r#"{"group_id":"g","peer_cid":5}"#);
+        assert!(scope.is_err());
+    }
 }